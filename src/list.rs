@@ -1,20 +1,121 @@
 use crate::{float::floating_window, running_command::Command, state::AppState};
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
-use ego_tree::{tree, NodeId};
+use ego_tree::{tree, NodeId, Tree};
 use ratatui::{
     layout::Rect,
-    style::{Style, Stylize},
-    text::Line,
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListState},
     Frame,
 };
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style as SynStyle, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// The syntect syntax/theme definitions, loaded once on first use and cached for the lifetime of
+/// the process. Building these is expensive enough that we don't want to redo it every time the
+/// preview window is toggled
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+    THEME_SET.get_or_init(ThemeSet::load_defaults)
+}
 
 #[derive(Clone)]
 struct ListNode {
-    name: &'static str,
+    name: String,
     command: Command,
 }
 
+/// A leaf entry that matched the current fuzzy filter query
+#[derive(Clone)]
+struct FilteredEntry {
+    id: NodeId,
+    node: ListNode,
+    /// Higher is a better match. Used to sort `filtered_items`
+    score: i64,
+    /// The char indices (into `node.name`) that matched the query, so `draw` can highlight them
+    match_indices: Vec<usize>,
+}
+
+/// Fuzzy subsequence match: every char of `query` must appear in `candidate`, in order, though
+/// not necessarily contiguously. Returns the match score and the matched char indices (into
+/// `candidate`) on success, or `None` if `query` isn't a subsequence of `candidate`.
+///
+/// Scoring rewards consecutive runs and matches right after a word boundary (start of string, or
+/// after a space/`-`), and lightly penalizes gaps between matches, so tighter, more "word-like"
+/// matches sort above scattered ones.
+fn fuzzy_match(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    if query.is_empty() {
+        return None;
+    }
+
+    let query_chars: Vec<char> = query.chars().collect();
+    let candidate_chars: Vec<char> = candidate.chars().collect();
+
+    let mut match_indices = Vec::with_capacity(query_chars.len());
+    let mut score: i64 = 0;
+    let mut query_idx = 0;
+    let mut prev_match_idx: Option<usize> = None;
+
+    for (i, &c) in candidate_chars.iter().enumerate() {
+        if query_idx == query_chars.len() {
+            break;
+        }
+        if c != query_chars[query_idx] {
+            continue;
+        }
+
+        score += 16;
+
+        if prev_match_idx.is_some_and(|p| p + 1 == i) {
+            // This match directly continues the previous one
+            score += 8;
+        }
+        if i == 0 || matches!(candidate_chars[i - 1], ' ' | '-') {
+            score += 8;
+        }
+        if let Some(p) = prev_match_idx {
+            score -= (i - p - 1) as i64;
+        }
+
+        match_indices.push(i);
+        prev_match_idx = Some(i);
+        query_idx += 1;
+    }
+
+    (query_idx == query_chars.len()).then_some((score, match_indices))
+}
+
+/// Builds a `Line` for a fuzzy-matched entry, bolding and underlining the chars of `name` that
+/// matched the filter query so the user can see why it matched
+fn highlighted_name_line(
+    prefix: String,
+    name: &str,
+    match_indices: &[usize],
+    base_style: Style,
+) -> Line<'static> {
+    let mut spans = vec![Span::styled(prefix, base_style)];
+    for (i, c) in name.chars().enumerate() {
+        let style = if match_indices.contains(&i) {
+            base_style.bold().underlined()
+        } else {
+            base_style
+        };
+        spans.push(Span::styled(c.to_string(), style));
+    }
+    Line::from(spans)
+}
+
 /// This is a data structure that has everything necessary to draw and manage a menu of commands
 pub struct CustomList {
     /// The tree data structure, to represent regular items
@@ -28,92 +129,492 @@ pub struct CustomList {
     list_state: ListState,
     /// This stores the preview windows state. If it is None, it will not be displayed.
     /// If it is Some, we show it with the content of the selected item
-    preview_window_state: Option<PreviewWindowState>,
+    preview_window_state: Option<FloatWindowState>,
+    /// This stores the keybinding help window state, toggled with `?`. Mutually exclusive with
+    /// `preview_window_state`: opening one closes the other
+    help_window_state: Option<FloatWindowState>,
+    /// Set when `state.config_path` failed to load, so we fell back to `default_tree()`. Shown
+    /// in the window title (rather than `eprintln!`'d) since stderr isn't visible while ratatui
+    /// owns the alternate screen
+    config_warning: Option<String>,
     // This stores the current search query
     filter_query: String,
-    // This stores the filtered tree
-    filtered_items: Vec<ListNode>,
+    // This stores the filtered tree, alongside the `NodeId` each entry was flattened from, so we
+    // can still flag/unflag matches while a filter is active
+    filtered_items: Vec<FilteredEntry>,
+    // Remembers the selected index within a directory, keyed by that directory's `NodeId`, so
+    // that going back up with `..` re-selects the entry we descended from instead of always
+    // landing on the first item
+    last_selected: HashMap<NodeId, usize>,
+    /// The set of commands "flagged" for batch execution. Toggled with `space`, and run
+    /// back-to-back when `Enter` is pressed while this is non-empty
+    flagged: HashSet<NodeId>,
+    /// The same entries as `flagged`, in the order they were flagged. `run_flagged` runs commands
+    /// from this, not from whatever directory/filter view happens to be on screen, so flags set
+    /// in one directory still run after navigating elsewhere
+    flag_order: Vec<NodeId>,
 }
 
-/// This struct stores the preview window state
-struct PreviewWindowState {
-    /// The text inside the window
-    text: Vec<String>,
+/// The state of a scrollable floating window of text, shared by the script preview and the
+/// keybinding help overlay
+struct FloatWindowState {
+    /// The lines inside the window, already styled (syntax-highlighted for the preview, plain
+    /// for the help overlay)
+    text: Vec<Line<'static>>,
     /// The current line scroll
     scroll: usize,
 }
 
-impl PreviewWindowState {
-    /// Create a new PreviewWindowState
-    pub fn new(text: Vec<String>) -> Self {
+impl FloatWindowState {
+    /// Create a new FloatWindowState
+    pub fn new(text: Vec<Line<'static>>) -> Self {
         Self { text, scroll: 0 }
     }
+
+    /// Scroll one line down, if there's more text below
+    fn scroll_down(&mut self) {
+        if self.scroll + 1 < self.text.len() {
+            self.scroll += 1;
+        }
+    }
+
+    /// Scroll one line up, if we're not already at the top
+    fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
 }
 
-impl CustomList {
-    pub fn new() -> Self {
-        // When a function call ends with an exclamation mark, it means it's a macro, like in this
-        // case the tree! macro expands to `ego-tree::tree` data structure
-        let tree = tree!(ListNode {
-            name: "root",
+/// Renders a `FloatWindowState` as a bordered floating window over `area`, titled `title`
+fn render_float_window(frame: &mut Frame, area: Rect, fw_state: &FloatWindowState, title: &str) {
+    // Set the window to be floating
+    let floating_area = floating_window(area);
+
+    // These lines are already styled, so we just need to slice out the visible, scrolled-to
+    // window
+    let lines: Vec<Line> = fw_state
+        .text
+        .iter()
+        .skip(fw_state.scroll)
+        .take(floating_area.height as usize)
+        .cloned()
+        .collect();
+
+    let list = List::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title.to_string()))
+        .highlight_style(Style::default().reversed());
+
+    frame.render_widget(list, floating_area);
+}
+
+/// Renders a `KeyCode` the way it should read in the help overlay
+fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+/// One row of the keybinding table: a key, what it does, and the action to run when it's pressed
+struct KeyBinding {
+    key: KeyCode,
+    description: &'static str,
+    action: fn(&mut CustomList, &AppState) -> Option<Vec<Command>>,
+}
+
+/// Single source of truth for every key this widget understands. `CustomList::handle_key`
+/// dispatches through this table, and the `?` help overlay is built straight from it, so the two
+/// can never drift out of sync with each other
+const KEYBINDINGS: &[KeyBinding] = &[
+    KeyBinding {
+        key: KeyCode::Char('j'),
+        description: "Move the selection down",
+        action: action_move_down,
+    },
+    KeyBinding {
+        key: KeyCode::Down,
+        description: "Move the selection down",
+        action: action_move_down,
+    },
+    KeyBinding {
+        key: KeyCode::Char('k'),
+        description: "Move the selection up",
+        action: action_move_up,
+    },
+    KeyBinding {
+        key: KeyCode::Up,
+        description: "Move the selection up",
+        action: action_move_up,
+    },
+    KeyBinding {
+        key: KeyCode::Char('p'),
+        description: "Toggle the script preview window",
+        action: action_toggle_preview,
+    },
+    KeyBinding {
+        key: KeyCode::Char('?'),
+        description: "Toggle this help window",
+        action: action_toggle_help,
+    },
+    KeyBinding {
+        key: KeyCode::Char(' '),
+        description: "Flag/unflag the selected entry for batch execution",
+        action: action_toggle_flag,
+    },
+    KeyBinding {
+        key: KeyCode::Char('c'),
+        description: "Clear every flag",
+        action: action_clear_flags,
+    },
+    KeyBinding {
+        key: KeyCode::Char('a'),
+        description: "Flag every command in the current view",
+        action: action_flag_all,
+    },
+    KeyBinding {
+        key: KeyCode::Char('v'),
+        description: "Invert the flags in the current view",
+        action: action_invert_flags,
+    },
+    KeyBinding {
+        key: KeyCode::Enter,
+        description: "Run the selection, enter/leave a directory, or run every flagged entry",
+        action: action_enter,
+    },
+];
+
+/// If a floating window (preview or help) is open, scroll it down; otherwise move the list
+/// selection down
+fn action_move_down(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    if let Some(fw_state) = list.active_float_state_mut() {
+        fw_state.scroll_down();
+        return None;
+    }
+    list.try_scroll_down();
+    None
+}
+
+/// If a floating window (preview or help) is open, scroll it up; otherwise move the list
+/// selection up
+fn action_move_up(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    if let Some(fw_state) = list.active_float_state_mut() {
+        fw_state.scroll_up();
+        return None;
+    }
+    list.try_scroll_up();
+    None
+}
+
+fn action_toggle_preview(list: &mut CustomList, state: &AppState) -> Option<Vec<Command>> {
+    list.toggle_preview_window(state);
+    None
+}
+
+fn action_toggle_help(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    list.toggle_help_window();
+    None
+}
+
+fn action_toggle_flag(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    list.toggle_flag();
+    None
+}
+
+fn action_clear_flags(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    list.clear_flags();
+    None
+}
+
+fn action_flag_all(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    list.flag_all();
+    None
+}
+
+fn action_invert_flags(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    list.invert_flags();
+    None
+}
+
+/// Runs the flagged commands if any are flagged, otherwise handles `Enter` as a regular
+/// select/navigate. Swallowed entirely while a floating window is open
+fn action_enter(list: &mut CustomList, _state: &AppState) -> Option<Vec<Command>> {
+    if list.preview_window_state.is_some() || list.help_window_state.is_some() {
+        return None;
+    }
+    if !list.flagged.is_empty() {
+        list.run_flagged()
+    } else {
+        list.handle_enter().map(|command| vec![command])
+    }
+}
+
+/// Highlights `source` as the given syntect `syntax` and converts it into ratatui `Line`s. Falls
+/// back to plain, unstyled lines if neither the syntax nor Bash, or the theme, can be found,
+/// instead of erroring out. Otherwise every span gets an RGB foreground color; the terminal's
+/// color support and the app theme are not consulted.
+fn highlight_script(source: &str, syntax_token: &str) -> Vec<Line<'static>> {
+    let syntax_set = syntax_set();
+    let syntax = syntax_set
+        .find_syntax_by_token(syntax_token)
+        .or_else(|| syntax_set.find_syntax_by_token("bash"));
+
+    let (Some(syntax), Some(theme)) = (
+        syntax,
+        theme_set().themes.get("base16-ocean.dark"),
+    ) else {
+        return source
+            .lines()
+            .map(|line| Line::from(line.to_string()))
+            .collect();
+    };
+
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    LinesWithEndings::from(source)
+        .map(|line| {
+            let spans = highlighter
+                .highlight_line(line, syntax_set)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(style, text)| {
+                    Span::styled(text.trim_end_matches(['\n', '\r']).to_string(), to_ratatui_style(style))
+                })
+                .collect::<Vec<_>>();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Maps a syntect foreground color to a ratatui one, ignoring the syntect background so the
+/// preview window keeps using the app's own background
+fn to_ratatui_style(style: SynStyle) -> Style {
+    let fg = style.foreground;
+    Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b))
+}
+
+/// Builds the hard-coded menu tree that ships with the binary. Used whenever `AppState::config_path`
+/// is unset, or when the config at that path fails to load, so there's always a usable toolbox.
+fn default_tree() -> Tree<ListNode> {
+    // When a function call ends with an exclamation mark, it means it's a macro, like in this
+    // case the tree! macro expands to `ego-tree::tree` data structure
+    tree!(ListNode {
+        name: "root".to_string(),
+        command: Command::None,
+    } => {
+        ListNode {
+            name: "System Setup".to_string(),
             command: Command::None,
         } => {
             ListNode {
-                name: "System Setup",
-                command: Command::None,
-            } => {
-                ListNode {
-                    name: "Build Prerequisites",
-                    command: Command::LocalFile("system-setup/1-compile-setup.sh"),
-                },
-                ListNode {
-                    name: "Gaming Dependencies",
-                    command: Command::LocalFile("system-setup/2-gaming-setup.sh"),
-                },
-                ListNode {
-                    name: "Global Theme",
-                    command: Command::LocalFile("system-setup/3-global-theme.sh"),
-                },
+                name: "Build Prerequisites".to_string(),
+                command: Command::LocalFile("system-setup/1-compile-setup.sh"),
             },
             ListNode {
-                name: "Security",
-                command: Command::None
-            } => {
-                ListNode {
-                    name: "Firewall Baselines (CTT)",
-                    command: Command::LocalFile("security/firewall-baselines.sh"),
-                }
+                name: "Gaming Dependencies".to_string(),
+                command: Command::LocalFile("system-setup/2-gaming-setup.sh"),
+            },
+            ListNode {
+                name: "Global Theme".to_string(),
+                command: Command::LocalFile("system-setup/3-global-theme.sh"),
+            },
+        },
+        ListNode {
+            name: "Security".to_string(),
+            command: Command::None
+        } => {
+            ListNode {
+                name: "Firewall Baselines (CTT)".to_string(),
+                command: Command::LocalFile("security/firewall-baselines.sh"),
+            }
+        },
+        ListNode {
+            name: "Applications Setup".to_string(),
+            command: Command::None
+        } => {
+            ListNode {
+                name: "Alacritty Setup".to_string(),
+                command: Command::LocalFile("applications-setup/alacritty-setup.sh"),
+            },
+            ListNode {
+                name: "Bash Prompt Setup".to_string(),
+                command: Command::Raw("bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/mybash/main/setup.sh)\""),
+            },
+            ListNode {
+                name: "Kitty Setup".to_string(),
+                command: Command::LocalFile("applications-setup/kitty-setup.sh")
             },
             ListNode {
-                name: "Applications Setup",
-                command: Command::None
-            } => {
-                ListNode {
-                    name: "Alacritty Setup",
-                    command: Command::LocalFile("applications-setup/alacritty-setup.sh"),
-                },
-                ListNode {
-                    name: "Bash Prompt Setup",
-                    command: Command::Raw("bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/mybash/main/setup.sh)\""),
-                },
-                ListNode {
-                    name: "Kitty Setup",
-                    command: Command::LocalFile("applications-setup/kitty-setup.sh")
-                },
-                ListNode {
-                    name: "Neovim Setup",
-                    command: Command::Raw("bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/neovim/main/setup.sh)\""),
-                },
-                ListNode {
-                    name: "Rofi Setup",
-                    command: Command::LocalFile("applications-setup/rofi-setup.sh"),
-                },
+                name: "Neovim Setup".to_string(),
+                command: Command::Raw("bash -c \"$(curl -s https://raw.githubusercontent.com/ChrisTitusTech/neovim/main/setup.sh)\""),
             },
             ListNode {
-                name: "Full System Update",
-                command: Command::LocalFile("system-update.sh"),
+                name: "Rofi Setup".to_string(),
+                command: Command::LocalFile("applications-setup/rofi-setup.sh"),
             },
-        });
+        },
+        ListNode {
+            name: "Full System Update".to_string(),
+            command: Command::LocalFile("system-update.sh"),
+        },
+    })
+}
+
+/// One entry of a user-supplied menu config (TOML or YAML). A "directory" has `children`, a
+/// command has exactly one of `raw`/`local_file`; anything else is a malformed entry.
+#[derive(Deserialize)]
+struct ConfigEntry {
+    name: String,
+    #[serde(default)]
+    raw: Option<String>,
+    #[serde(default)]
+    local_file: Option<String>,
+    #[serde(default)]
+    children: Vec<ConfigEntry>,
+}
+
+/// Top-level shape of the config file: a flat list of root entries
+#[derive(Deserialize)]
+struct Config {
+    #[serde(default)]
+    entry: Vec<ConfigEntry>,
+}
+
+/// Parses `path` as TOML (the default) or YAML (`.yaml`/`.yml`) and builds the equivalent
+/// `ego_tree::Tree<ListNode>`, validating every entry and every `local_file` script along the
+/// way. `scripts_root` is the directory `local_file` paths are resolved against, same as
+/// `toggle_preview_window` resolves them against `state.temp_path`.
+///
+/// Returns a human-readable error, rather than a dedicated error type, so it can be shown to the
+/// user (or logged) verbatim by the caller, same as the rest of this module's ad hoc error
+/// handling.
+fn load_tree_from_config(path: &Path, scripts_root: &Path) -> Result<Tree<ListNode>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read config {path:?}: {e}"))?;
+
+    let config: Config = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)
+            .map_err(|e| format!("failed to parse config {path:?} as YAML: {e}"))?,
+        _ => toml::from_str(&contents)
+            .map_err(|e| format!("failed to parse config {path:?} as TOML: {e}"))?,
+    };
+
+    if config.entry.is_empty() {
+        return Err(format!("config {path:?} declares no entries"));
+    }
+
+    // Canonicalize once so every `local_file` below can be checked against the real,
+    // symlink-resolved scripts directory instead of whatever `scripts_root` happens to look like
+    // on paper
+    let canonical_scripts_root = scripts_root
+        .canonicalize()
+        .map_err(|e| format!("failed to resolve scripts directory {scripts_root:?}: {e}"))?;
+
+    let mut tree = tree!(ListNode {
+        name: "root".to_string(),
+        command: Command::None,
+    });
+    let root_id = tree.root().id();
+    for entry in config.entry {
+        append_config_entry(&mut tree, root_id, entry, &canonical_scripts_root)?;
+    }
+    Ok(tree)
+}
+
+/// Validates and inserts a single config entry (and its children, recursively) under `parent`.
+/// `scripts_root` must already be canonicalized.
+fn append_config_entry(
+    tree: &mut Tree<ListNode>,
+    parent: NodeId,
+    entry: ConfigEntry,
+    scripts_root: &Path,
+) -> Result<(), String> {
+    if entry.raw.is_some() && entry.local_file.is_some() {
+        return Err(format!(
+            "entry {:?} must set at most one of `raw`/`local_file`, not both",
+            entry.name
+        ));
+    }
+
+    let has_command = entry.raw.is_some() || entry.local_file.is_some();
+    let has_children = !entry.children.is_empty();
+    if has_command == has_children {
+        return Err(format!(
+            "entry {:?} must have exactly one of a command (`raw`/`local_file`) or `children`",
+            entry.name
+        ));
+    }
+
+    let command = if let Some(raw) = &entry.raw {
+        // `Command::Raw` expects a `&'static str`, same as the hard-coded tree's literals. The
+        // config is only ever loaded once at startup, so leaking these few strings for the
+        // lifetime of the process is a fine trade for not having to thread lifetimes through
+        // `ListNode`/`Command`.
+        Command::Raw(leak_static(raw))
+    } else if let Some(local_file) = &entry.local_file {
+        let full_path = scripts_root.join(local_file);
+        // `canonicalize` both resolves symlinks and requires the path to exist, so this also
+        // covers the missing-script check. Rejecting anything that escapes `scripts_root` (via
+        // an absolute `local_file`, `../..`, or a symlink) stops a shared config from pointing at
+        // arbitrary files outside the toolbox directory.
+        let canonical_path = full_path.canonicalize().map_err(|_| {
+            format!("entry {:?} points at a missing script: {:?}", entry.name, full_path)
+        })?;
+        if !canonical_path.starts_with(scripts_root) {
+            return Err(format!(
+                "entry {:?} points outside the scripts directory: {:?}",
+                entry.name, local_file
+            ));
+        }
+        if !canonical_path.is_file() {
+            return Err(format!(
+                "entry {:?} points at a non-file path: {:?}",
+                entry.name, full_path
+            ));
+        }
+        Command::LocalFile(leak_static(local_file))
+    } else {
+        Command::None
+    };
+
+    let node_id = tree
+        .get_mut(parent)
+        .unwrap()
+        .append(ListNode {
+            name: entry.name,
+            command,
+        })
+        .id();
+    for child in entry.children {
+        append_config_entry(tree, node_id, child, scripts_root)?;
+    }
+    Ok(())
+}
+
+/// Leaks an owned string to produce a `&'static str`. See the note in `append_config_entry` for
+/// why that's an acceptable trade-off for config entries, which are only ever parsed once.
+fn leak_static(s: &str) -> &'static str {
+    Box::leak(s.to_string().into_boxed_str())
+}
+
+impl CustomList {
+    /// Builds the menu tree from `state.config_path` if it's set and loads successfully,
+    /// otherwise falls back to the built-in `default_tree()`
+    pub fn new(state: &AppState) -> Self {
+        let mut config_warning = None;
+        let tree = match &state.config_path {
+            Some(config_path) => {
+                match load_tree_from_config(config_path, &state.temp_path) {
+                    Ok(tree) => tree,
+                    Err(err) => {
+                        config_warning = Some(format!("{err}, falling back to the built-in menu"));
+                        default_tree()
+                    }
+                }
+            }
+            None => default_tree(),
+        };
         // We don't get a reference, but rather an id, because references are siginficantly more
         // paintfull to manage
         let root_id = tree.root().id();
@@ -121,10 +622,15 @@ impl CustomList {
             inner_tree: tree,
             visit_stack: vec![root_id],
             list_state: ListState::default().with_selected(Some(0)),
-            // By default the PreviewWindowState is set to None, so it is not being shown
+            // By default the FloatWindowState is set to None, so it is not being shown
             preview_window_state: None,
+            help_window_state: None,
+            config_warning,
             filter_query: String::new(),
             filtered_items: vec![],
+            last_selected: HashMap::new(),
+            flagged: HashSet::new(),
+            flag_order: Vec::new(),
         }
     }
 
@@ -152,41 +658,70 @@ impl CustomList {
 
             // Iterate through all the children
             for node in curr.children() {
+                let flag_marker = if self.flagged.contains(&node.id()) {
+                    "[x] "
+                } else {
+                    ""
+                };
                 // The difference between a "directory" and a "command" is simple: if it has children,
                 // it's a directory and will be handled as such
                 if node.has_children() {
                     items.push(
-                        Line::from(format!("{}  {}", state.theme.dir_icon, node.value().name))
-                            .style(state.theme.dir_color),
+                        Line::from(format!(
+                            "{}{}  {}",
+                            flag_marker, state.theme.dir_icon, node.value().name
+                        ))
+                        .style(state.theme.dir_color),
                     );
                 } else {
-                    items.push(
-                        Line::from(format!("{}  {}", state.theme.cmd_icon, node.value().name))
-                            .style(state.theme.cmd_color),
-                    );
+                    let style = if self.flagged.contains(&node.id()) {
+                        state.theme.cmd_color.yellow().bold()
+                    } else {
+                        state.theme.cmd_color
+                    };
+                    items.push(Line::from(format!(
+                        "{}{}  {}",
+                        flag_marker, state.theme.cmd_icon, node.value().name
+                    ))
+                    .style(style));
                 }
             }
             items
         } else {
-            let mut sorted_items = self.filtered_items.clone();
-            sorted_items.sort_by(|a, b| a.name.cmp(b.name));
-            sorted_items
+            // `filtered_items` is already sorted best-match-first by `filter`
+            self.filtered_items
                 .iter()
-                .map(|node| {
-                    Line::from(format!("{}  {}", state.theme.cmd_icon, node.name))
-                        .style(state.theme.cmd_color)
+                .map(|entry| {
+                    let flag_marker = if self.flagged.contains(&entry.id) {
+                        "[x] "
+                    } else {
+                        ""
+                    };
+                    let style = if self.flagged.contains(&entry.id) {
+                        state.theme.cmd_color.yellow().bold()
+                    } else {
+                        state.theme.cmd_color
+                    };
+                    let prefix = format!("{}{}  ", flag_marker, state.theme.cmd_icon);
+                    highlighted_name_line(prefix, &entry.node.name, &entry.match_indices, style)
                 })
                 .collect()
         };
 
         // create the normal list widget containing only item in our "working directory" / tree
         // node
+        let title = match &self.config_warning {
+            // Surfaced in the title bar, rather than `eprintln!`'d, since stderr isn't visible
+            // while ratatui owns the alternate screen
+            Some(warning) => format!(
+                "Linux Toolbox - {} - config warning: {warning}",
+                chrono::Local::now().format("%Y-%m-%d")
+            ),
+            None => format!("Linux Toolbox - {}", chrono::Local::now().format("%Y-%m-%d")),
+        };
         let list = List::new(item_list)
             .highlight_style(Style::default().reversed())
-            .block(Block::default().borders(Borders::ALL).title(format!(
-                "Linux Toolbox - {}",
-                chrono::Local::now().format("%Y-%m-%d")
-            )))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .scroll_padding(1);
 
         // Render it
@@ -194,29 +729,13 @@ impl CustomList {
 
         // Draw the preview window if it's active
         if let Some(pw_state) = &self.preview_window_state {
-            // Set the window to be floating
-            let floating_area = floating_window(area);
-
-            // Draw the preview windows lines
-            let lines: Vec<Line> = pw_state
-                .text
-                .iter()
-                .skip(pw_state.scroll)
-                .take(floating_area.height as usize)
-                .map(|line| Line::from(line.as_str()))
-                .collect();
+            render_float_window(frame, area, pw_state, "Action preview");
+        }
 
-            // Create list widget
-            let list = List::new(lines)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title("Action preview"),
-                )
-                .highlight_style(Style::default().reversed());
-
-            // Finally render the preview window
-            frame.render_widget(list, floating_area);
+        // Draw the keybinding help window if it's active. Mutually exclusive with the preview
+        // window, but we don't rely on that here, we just draw whichever is set
+        if let Some(help_state) = &self.help_window_state {
+            render_float_window(frame, area, help_state, "Keybindings");
         }
     }
 
@@ -230,14 +749,27 @@ impl CustomList {
         while let Some(node_id) = stack.pop() {
             let node = self.inner_tree.get(node_id).unwrap();
 
-            if node.value().name.to_lowercase().contains(&query_lower) && !node.has_children() {
-                self.filtered_items.push(node.value().clone());
+            if !node.has_children() {
+                if let Some((score, match_indices)) =
+                    fuzzy_match(&query_lower, &node.value().name.to_lowercase())
+                {
+                    self.filtered_items.push(FilteredEntry {
+                        id: node_id,
+                        node: node.value().clone(),
+                        score,
+                        match_indices,
+                    });
+                }
             }
 
             for child in node.children() {
                 stack.push(child.id());
             }
         }
+
+        // Best match first; ties broken alphabetically for a stable, predictable order
+        self.filtered_items
+            .sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.node.name.cmp(&b.node.name)));
     }
 
     /// Resets the selection to the first item
@@ -250,64 +782,46 @@ impl CustomList {
     }
 
     /// Handle key events, we are only interested in `Press` and `Repeat` events
-    pub fn handle_key(&mut self, event: KeyEvent, state: &AppState) -> Option<Command> {
+    ///
+    /// Dispatches through `KEYBINDINGS`, so every key this widget reacts to (and the help text
+    /// for it) lives in that one table.
+    ///
+    /// Returns the ordered list of commands to run, if any. This is usually a single command,
+    /// but pressing `Enter` while one or more entries are flagged returns all of them so they can
+    /// be executed back-to-back
+    pub fn handle_key(&mut self, event: KeyEvent, state: &AppState) -> Option<Vec<Command>> {
         if event.kind == KeyEventKind::Release {
             return None;
         }
-        match event.code {
-            // Damm you Up arrow, use vim lol
-            KeyCode::Char('j') | KeyCode::Down => {
-                // If the preview window is active, scroll down and consume the scroll action,
-                // so the scroll does not happen in the main window as well
-                if self.preview_window_state.is_some() {
-                    self.scroll_preview_window_down();
-                    return None;
-                }
-
-                self.try_scroll_down();
-                None
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                // If the preview window is active, scroll up and consume the scroll action,
-                // so the scroll does not happen in the main window as well
-                if self.preview_window_state.is_some() {
-                    self.scroll_preview_window_up();
-                    return None;
-                }
-
-                self.try_scroll_up();
-                None
-            }
-            // The 'p' key toggles the preview on and off
-            KeyCode::Char('p') => {
-                self.toggle_preview_window(state);
-                None
-            }
+        let binding = KEYBINDINGS.iter().find(|binding| binding.key == event.code)?;
+        (binding.action)(self, state)
+    }
 
-            KeyCode::Enter => { 
-                if self.preview_window_state.is_none() {
-                    self.handle_enter()
-                } else {
-                    None
-                }
-            }
-            _ => None,
-        }
+    /// Returns whichever floating window (preview or help) is currently open, if any. `j`/`k`
+    /// scroll this instead of moving the list selection while it's open.
+    fn active_float_state_mut(&mut self) -> Option<&mut FloatWindowState> {
+        self.preview_window_state
+            .as_mut()
+            .or(self.help_window_state.as_mut())
     }
+
     fn toggle_preview_window(&mut self, state: &AppState) {
         // If the preview window is active, disable it
         if self.preview_window_state.is_some() {
             self.preview_window_state = None;
         } else {
-            // If the preview windows is not active, show it
+            // If the preview windows is not active, show it. Mutually exclusive with the help
+            // window
+            self.help_window_state = None;
 
             // Get the selected command
             if let Some(selected_command) = self.get_selected_command() {
-                let lines = match selected_command {
+                let (source, syntax_token) = match selected_command {
                     Command::Raw(cmd) => {
                         // Reconstruct the line breaks and file formatting after the
-                        // 'include_str!()' call in the node
-                        cmd.lines().map(|line| line.to_string()).collect()
+                        // 'include_str!()' call in the node. There's no file extension to sniff
+                        // here, so we assume Bash, same as the vast majority of our scripts
+                        (cmd.to_string(), "bash".to_string())
                     }
                     Command::LocalFile(file_path) => {
                         let mut full_path = state.temp_path.clone();
@@ -315,18 +829,42 @@ impl CustomList {
                         let file_contents = std::fs::read_to_string(&full_path)
                             .map_err(|_| format!("File not found: {:?}", &full_path))
                             .unwrap();
-                        file_contents.lines().map(|line| line.to_string()).collect()
+                        let syntax_token = full_path
+                            .extension()
+                            .and_then(|ext| ext.to_str())
+                            .unwrap_or("sh");
+                        (file_contents, syntax_token.to_string())
                     }
                     // If command is a folder, we don't display a preview
                     Command::None => return,
                 };
 
-                // Show the preview window with the text lines
-                self.preview_window_state = Some(PreviewWindowState::new(lines));
+                // Show the preview window with the syntax-highlighted text
+                let lines = highlight_script(&source, &syntax_token);
+                self.preview_window_state = Some(FloatWindowState::new(lines));
             }
         }
     }
 
+    /// Toggles the keybinding help overlay, built straight from `KEYBINDINGS` so it can never
+    /// list a binding that doesn't actually exist (or omit one that does)
+    fn toggle_help_window(&mut self) {
+        if self.help_window_state.is_some() {
+            self.help_window_state = None;
+        } else {
+            // Mutually exclusive with the preview window
+            self.preview_window_state = None;
+
+            let lines = KEYBINDINGS
+                .iter()
+                .map(|binding| {
+                    Line::from(format!("{:<8} {}", key_label(binding.key), binding.description))
+                })
+                .collect();
+            self.help_window_state = Some(FloatWindowState::new(lines));
+        }
+    }
+
     fn try_scroll_up(&mut self) {
         if let Some(selected) = self.list_state.selected() {
             if selected > 0 {
@@ -348,6 +886,10 @@ impl CustomList {
 
         if let Some(curr_selection) = self.list_state.selected() {
             if self.at_root() {
+                // An empty view (e.g. a filter query that matches nothing) has nothing to scroll to
+                if count == 0 {
+                    return;
+                }
                 self.list_state
                     .select(Some((curr_selection + 1).min(count - 1)));
             } else {
@@ -359,24 +901,6 @@ impl CustomList {
         }
     }
 
-    /// Scroll the preview window down
-    fn scroll_preview_window_down(&mut self) {
-        if let Some(pw_state) = &mut self.preview_window_state {
-            if pw_state.scroll + 1 < pw_state.text.len() {
-                pw_state.scroll += 1;
-            }
-        }
-    }
-
-    /// Scroll the preview window up
-    fn scroll_preview_window_up(&mut self) {
-        if let Some(pw_state) = &mut self.preview_window_state {
-            if pw_state.scroll > 0 {
-                pw_state.scroll = pw_state.scroll.saturating_sub(1);
-            }
-        }
-    }
-
     /// This method returns the currently selected command, or None if no command is selected.
     /// It was extracted from the 'handle_enter()'
     ///
@@ -407,13 +931,139 @@ impl CustomList {
             }
         } else {
             // Filter query is active, use the filtered items
-            if let Some(filtered_node) = self.filtered_items.get(selected) {
-                return Some(filtered_node.command.clone());
+            if let Some(entry) = self.filtered_items.get(selected) {
+                return Some(entry.node.command.clone());
             }
         }
         None
     }
 
+    /// Returns the `NodeId` of the currently selected entry, if any. Used to flag/unflag the
+    /// selection without caring whether we're browsing the tree or a filtered view
+    fn get_selected_id(&self) -> Option<NodeId> {
+        let selected = self.list_state.selected()?;
+
+        if self.filter_query.is_empty() {
+            let curr = self
+                .inner_tree
+                .get(*self.visit_stack.last().unwrap())
+                .unwrap();
+
+            if !self.at_root() && selected == 0 {
+                return None;
+            }
+
+            for (mut idx, node) in curr.children().enumerate() {
+                if !self.at_root() {
+                    idx += 1;
+                }
+                if idx == selected {
+                    return Some(node.id());
+                }
+            }
+            None
+        } else {
+            self.filtered_items.get(selected).map(|entry| entry.id)
+        }
+    }
+
+    /// Flags `id`, recording it at the end of `flag_order` if it wasn't already flagged
+    fn flag(&mut self, id: NodeId) {
+        if self.flagged.insert(id) {
+            self.flag_order.push(id);
+        }
+    }
+
+    /// Unflags `id`, removing it from `flag_order` as well
+    fn unflag(&mut self, id: NodeId) {
+        if self.flagged.remove(&id) {
+            self.flag_order.retain(|&flagged_id| flagged_id != id);
+        }
+    }
+
+    /// Toggles the flag on the currently selected entry. Directories can't be flagged, since
+    /// there is no single command to run for them
+    fn toggle_flag(&mut self) {
+        if let Some(id) = self.get_selected_id() {
+            if self.inner_tree.get(id).unwrap().has_children() {
+                return;
+            }
+            if self.flagged.contains(&id) {
+                self.unflag(id);
+            } else {
+                self.flag(id);
+            }
+        }
+    }
+
+    /// Clears every flag, in the whole tree, not just the current directory
+    fn clear_flags(&mut self) {
+        self.flagged.clear();
+        self.flag_order.clear();
+    }
+
+    /// Flags every command directly inside the current directory (or filtered view)
+    fn flag_all(&mut self) {
+        for id in self.current_entry_ids() {
+            if !self.inner_tree.get(id).unwrap().has_children() {
+                self.flag(id);
+            }
+        }
+    }
+
+    /// Inverts the flags of every command directly inside the current directory (or filtered
+    /// view): flagged entries become unflagged and vice versa
+    fn invert_flags(&mut self) {
+        for id in self.current_entry_ids() {
+            if self.inner_tree.get(id).unwrap().has_children() {
+                continue;
+            }
+            if self.flagged.contains(&id) {
+                self.unflag(id);
+            } else {
+                self.flag(id);
+            }
+        }
+    }
+
+    /// The `NodeId`s of every entry in the view we're currently showing (tree children of the
+    /// current directory, or the filtered items)
+    fn current_entry_ids(&self) -> Vec<NodeId> {
+        if self.filter_query.is_empty() {
+            let curr = self
+                .inner_tree
+                .get(*self.visit_stack.last().unwrap())
+                .unwrap();
+            curr.children().map(|node| node.id()).collect()
+        } else {
+            self.filtered_items.iter().map(|entry| entry.id).collect()
+        }
+    }
+
+    /// Collects the commands of every flagged entry, in the order they were flagged, and clears
+    /// the flags. Runs across the whole tree, not just the directory/filter view we're currently
+    /// looking at, since flags can be set in one directory and run from another
+    fn run_flagged(&mut self) -> Option<Vec<Command>> {
+        if self.flagged.is_empty() {
+            return None;
+        }
+
+        let commands: Vec<Command> = self
+            .flag_order
+            .iter()
+            .map(|id| self.inner_tree.get(*id).unwrap().value().command.clone())
+            .collect();
+
+        self.flagged.clear();
+        self.flag_order.clear();
+
+        if commands.is_empty() {
+            None
+        } else {
+            Some(commands)
+        }
+    }
+
     /// Handles the <Enter> key. This key can do 3 things:
     /// - Run a command, if it is the currently selected item,
     /// - Go up a directory
@@ -440,7 +1090,9 @@ impl CustomList {
             // we can be sure it's '..', so we go up the directory
             if !self.at_root() && selected == 0 {
                 self.visit_stack.pop();
-                self.list_state.select(Some(0));
+                let parent_id = *self.visit_stack.last().unwrap();
+                let restored = self.last_selected.get(&parent_id).copied().unwrap_or(0);
+                self.list_state.select(Some(restored));
                 return None;
             }
 
@@ -452,6 +1104,9 @@ impl CustomList {
                 }
                 if idx == selected {
                     if node.has_children() {
+                        // Remember which child of the current directory we're descending into,
+                        // so `..` can restore this exact selection later
+                        self.last_selected.insert(*self.visit_stack.last().unwrap(), selected);
                         self.visit_stack.push(node.id());
                         self.list_state.select(Some(0));
                         return None;
@@ -462,8 +1117,8 @@ impl CustomList {
             }
         } else {
             // Filter query is active, use the filtered items
-            if let Some(filtered_node) = self.filtered_items.get(selected) {
-                return Some(filtered_node.command.clone());
+            if let Some(entry) = self.filtered_items.get(selected) {
+                return Some(entry.node.command.clone());
             }
         }
         None